@@ -0,0 +1,97 @@
+// Copyright 2020 Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `Read`/`Write` wrappers that compute a running CRC64 checksum of the bytes that pass
+//! through them, without buffering or otherwise altering the underlying stream.
+
+use std::io::{Read, Result, Write};
+
+/// A `Write` wrapper that transparently updates a CRC64 checksum with every byte written.
+pub struct CRC64Writer<W: Write> {
+    writer: W,
+    checksum: u64,
+}
+
+impl<W: Write> CRC64Writer<W> {
+    /// Creates a new `CRC64Writer` that wraps `writer`.
+    pub fn new(writer: W) -> Self {
+        CRC64Writer {
+            writer,
+            checksum: 0,
+        }
+    }
+
+    /// Returns the CRC64 checksum of all the bytes written so far.
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+
+    /// Consumes this wrapper, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for CRC64Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.checksum = crc64::crc64(self.checksum, &buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A `Read` wrapper that transparently updates a CRC64 checksum with every byte read.
+pub struct CRC64Reader<R: Read> {
+    reader: R,
+    checksum: u64,
+}
+
+impl<R: Read> CRC64Reader<R> {
+    /// Creates a new `CRC64Reader` that wraps `reader`.
+    pub fn new(reader: R) -> Self {
+        CRC64Reader {
+            reader,
+            checksum: 0,
+        }
+    }
+
+    /// Returns the CRC64 checksum of all the bytes read so far.
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+}
+
+impl<R: Read> Read for CRC64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.checksum = crc64::crc64(self.checksum, &buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc64_writer_reader_agree() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+
+        let mut buf = Vec::new();
+        let mut writer = CRC64Writer::new(&mut buf);
+        writer.write_all(data).unwrap();
+        let write_checksum = writer.checksum();
+
+        let mut reader = CRC64Reader::new(buf.as_slice());
+        let mut read_back = Vec::new();
+        reader.read_to_end(&mut read_back).unwrap();
+
+        assert_eq!(read_back, data);
+        assert_eq!(write_checksum, reader.checksum());
+        assert_eq!(write_checksum, crc64::crc64(0, data));
+    }
+}
@@ -23,6 +23,35 @@
 //! - Versionize trait implementations for repr(C) unions must be backed by extensive testing.
 //! - Semantic serialization and deserialization is available only for structures.
 //!
+//! The `#[version(start = "...", end = "...")]` attribute accepts a comma-separated
+//! `major.minor.patch` bound per maintained release branch (e.g. `start = "2.7.7, 2.8.3"`),
+//! so a single field definition can describe when it was backported on each branch. See
+//! [`version_map::field_present_for_branches`] for how a bound list is resolved against a
+//! crate's target version. Note: the attribute-parsing side of this lives in the
+//! `versionize_derive` proc-macro crate, which is not part of this tree; only the runtime
+//! resolution helpers the generated code calls into are implemented here.
+//!
+//! The `#[version(req = "...")]` attribute gates a field with a full `semver::VersionReq`
+//! instead of a start/end range, for compatibility windows a single range can't express
+//! (e.g. `">=2.7, <2.9"`). See [`version_map::field_present_for_req`].
+//!
+//! A field whose meaning changes between versions can register `default_fn`, `semantic_de`
+//! and `semantic_ser` closures via its `#[version(...)]` attribute; the generated code wires
+//! them up as [`migration::SemanticHook`]s and runs them through [`Versionize::semantic_de`]
+//! and [`Versionize::semantic_ser`] (backed by [`migration::run_semantic_de`] and
+//! [`migration::run_semantic_ser`]) to progressively migrate a value written by an old binary
+//! to the current in-memory layout, one version at a time, and back down when serializing for
+//! an older reader.
+//!
+//! The [`snapshot`] module builds a self-describing container format on top of `Versionize`:
+//! it embeds the crate versions a payload was written with and a CRC64 trailer, so a
+//! snapshot can be loaded without out-of-band knowledge of the `VersionMap` it was produced
+//! with.
+//!
+//! `Versionize for semver::Version` round-trips `-pre`/`+build` metadata by default, which
+//! production snapshot pipelines can disable via [`VersionMap::strict_release_only`] to
+//! restrict themselves to release versions only.
+//!
 extern crate bincode;
 extern crate crc64;
 extern crate serde;
@@ -31,7 +60,9 @@ extern crate versionize_derive;
 extern crate vmm_sys_util;
 
 pub mod crc;
+pub mod migration;
 pub mod primitives;
+pub mod snapshot;
 pub mod version_map;
 
 use std::any::TypeId;
@@ -54,6 +85,19 @@ pub enum VersionizeError {
     StringLength(usize),
     /// Vector length exceeded.
     VecLength(usize),
+    /// An unsupported version was specified for serialization/deserialization.
+    UnsuportVersion(String),
+    /// A semver string failed to parse. Contains the offending string and the parser error.
+    ParseVersion(String, String),
+    /// The crate version map has no entry for the given crate.
+    NotFoundCrate(String),
+    /// The same crate was registered in the version map with two different versions.
+    MultipleVersion(String, String, String),
+    /// A snapshot's magic number did not match the expected value.
+    InvalidMagic(u32),
+    /// A snapshot's CRC64 trailer did not match the checksum computed over its contents.
+    /// Contains the `(expected, actual)` checksums.
+    Crc(u64, u64),
 }
 
 impl std::fmt::Display for VersionizeError {
@@ -77,6 +121,20 @@ impl std::fmt::Display for VersionizeError {
                 bad_len,
                 primitives::MAX_VEC_SIZE
             ),
+            UnsuportVersion(ver) => write!(f, "Unsupported version: {}", ver),
+            ParseVersion(ver, err) => write!(f, "Failed to parse semver {}: {}", ver, err),
+            NotFoundCrate(name) => write!(f, "No version found for crate {}", name),
+            MultipleVersion(name, v1, v2) => write!(
+                f,
+                "Crate {} was registered with two different versions: {} and {}",
+                name, v1, v2
+            ),
+            InvalidMagic(magic) => write!(f, "Invalid snapshot magic number: {:#x}", magic),
+            Crc(expected, actual) => write!(
+                f,
+                "Snapshot CRC64 mismatch: expected {:#x}, computed {:#x}",
+                expected, actual
+            ),
         }
     }
 }
@@ -114,6 +172,42 @@ pub trait Versionize {
 
     /// Returns latest `Self` version number.
     fn version() -> u16;
+
+    /// Runs registered semantic migration hooks after `deserialize`, upgrading `self` from
+    /// the on-disk representation written by `source_version` to the current in-memory
+    /// layout.
+    ///
+    /// A `#[version(start = "...", default_fn = "...", semantic_de = "...")]` attribute on a
+    /// field registers a [`migration::SemanticHook`] that runs here when that field was
+    /// absent in `source_version` (and was populated from `default_fn` instead). The
+    /// generated implementation resolves `source_version` against `version_map` the same way
+    /// [`Self::serialize`]/[`Self::deserialize`] do, then calls
+    /// [`migration::run_semantic_de`], which invokes every registered hook in ascending
+    /// version order so a value written by an old binary is progressively upgraded one
+    /// version at a time.
+    fn semantic_de(
+        &mut self,
+        _version_map: &VersionMap,
+        _source_version: u16,
+    ) -> VersionizeResult<()> {
+        Ok(())
+    }
+
+    /// Runs registered semantic migration hooks before `serialize`, down-converting `self`
+    /// to a representation compatible with readers at `target_version`.
+    ///
+    /// A `#[version(start = "...", semantic_ser = "...")]` attribute on a field registers a
+    /// [`migration::SemanticHook`] that runs here when that field will be absent in
+    /// `target_version`. The generated implementation resolves `target_version` against
+    /// `version_map` and calls [`migration::run_semantic_ser`], which invokes every
+    /// registered hook in descending version order, down-converting one version at a time.
+    fn semantic_ser(
+        &mut self,
+        _version_map: &VersionMap,
+        _target_version: u16,
+    ) -> VersionizeResult<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
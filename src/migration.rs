@@ -0,0 +1,277 @@
+// Copyright 2020 Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A semantic migration engine for fields whose meaning changes across versions.
+//!
+//! A `#[version(start = "...", default_fn = "...", semantic_de = "...", semantic_ser =
+//! "...")]` attribute on a field registers a [`SemanticHook`] for its owning type, keyed to
+//! the version the field was introduced at. [`run_semantic_de`] and [`run_semantic_ser`] are
+//! the engine the generated [`crate::Versionize::semantic_de`]/[`crate::Versionize::semantic_ser`]
+//! implementations call into: they order the registered hooks and invoke the ones that apply
+//! to the version being migrated from/to, so a value written by an old binary is upgraded one
+//! version at a time, and down-converted the same way when serializing for an older reader.
+
+use crate::VersionizeResult;
+
+/// A single semantic migration hook, registered against the version at which the field it
+/// migrates was introduced.
+pub struct SemanticHook<T> {
+    /// The version the associated field started existing at.
+    pub version: semver::Version,
+    /// Runs on deserialize when the field was absent in the source version (after it has been
+    /// populated from the field's `default_fn`), upgrading it to the current representation.
+    pub semantic_de: Option<fn(&mut T, &semver::Version) -> VersionizeResult<()>>,
+    /// Runs on serialize when the field will be absent in the target version, down-converting
+    /// it to a representation compatible with older readers.
+    pub semantic_ser: Option<fn(&mut T, &semver::Version) -> VersionizeResult<()>>,
+}
+
+/// Runs every hook in `hooks` whose field was introduced after `source_version`, in ascending
+/// version order, so a value is upgraded one version at a time rather than all at once.
+pub fn run_semantic_de<T>(
+    obj: &mut T,
+    hooks: &mut [SemanticHook<T>],
+    source_version: &semver::Version,
+) -> VersionizeResult<()> {
+    hooks.sort_by(|a, b| a.version.cmp(&b.version));
+    for hook in hooks.iter() {
+        if hook.version <= *source_version {
+            continue;
+        }
+        if let Some(semantic_de) = hook.semantic_de {
+            semantic_de(obj, source_version)?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs every hook in `hooks` whose field was introduced after `target_version`, in
+/// descending version order, so a value is down-converted one version at a time rather than
+/// all at once.
+pub fn run_semantic_ser<T>(
+    obj: &mut T,
+    hooks: &mut [SemanticHook<T>],
+    target_version: &semver::Version,
+) -> VersionizeResult<()> {
+    hooks.sort_by(|a, b| b.version.cmp(&a.version));
+    for hook in hooks.iter() {
+        if hook.version <= *target_version {
+            continue;
+        }
+        if let Some(semantic_ser) = hook.semantic_ser {
+            semantic_ser(obj, target_version)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VersionMap, VersionizeError};
+
+    #[derive(Debug, PartialEq, Default)]
+    struct Dummy {
+        // Tracks the order in which hooks ran, to assert on it.
+        log: Vec<&'static str>,
+    }
+
+    fn hook(version: &str, tag: &'static str) -> SemanticHook<Dummy> {
+        SemanticHook {
+            version: semver::Version::parse(version).unwrap(),
+            semantic_de: Some(match tag {
+                "v2" => |obj: &mut Dummy, _: &semver::Version| -> VersionizeResult<()> {
+                    obj.log.push("v2");
+                    Ok(())
+                },
+                "v3" => |obj: &mut Dummy, _: &semver::Version| -> VersionizeResult<()> {
+                    obj.log.push("v3");
+                    Ok(())
+                },
+                _ => unreachable!(),
+            }),
+            semantic_ser: Some(match tag {
+                "v2" => |obj: &mut Dummy, _: &semver::Version| -> VersionizeResult<()> {
+                    obj.log.push("v2");
+                    Ok(())
+                },
+                "v3" => |obj: &mut Dummy, _: &semver::Version| -> VersionizeResult<()> {
+                    obj.log.push("v3");
+                    Ok(())
+                },
+                _ => unreachable!(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_run_semantic_de_ascending_order() {
+        let mut obj = Dummy::default();
+        let mut hooks = vec![hook("2.3.0", "v3"), hook("2.2.0", "v2")];
+
+        run_semantic_de(&mut obj, &mut hooks, &semver::Version::parse("2.1.0").unwrap()).unwrap();
+
+        assert_eq!(obj.log, vec!["v2", "v3"]);
+    }
+
+    #[test]
+    fn test_run_semantic_de_skips_hooks_already_present_at_source() {
+        let mut obj = Dummy::default();
+        let mut hooks = vec![hook("2.3.0", "v3"), hook("2.2.0", "v2")];
+
+        // Source already has the 2.2.0 field; only the 2.3.0 hook should run.
+        run_semantic_de(&mut obj, &mut hooks, &semver::Version::parse("2.2.0").unwrap()).unwrap();
+
+        assert_eq!(obj.log, vec!["v3"]);
+    }
+
+    #[test]
+    fn test_run_semantic_ser_descending_order() {
+        let mut obj = Dummy::default();
+        let mut hooks = vec![hook("2.2.0", "v2"), hook("2.3.0", "v3")];
+
+        run_semantic_ser(&mut obj, &mut hooks, &semver::Version::parse("2.1.0").unwrap()).unwrap();
+
+        assert_eq!(obj.log, vec!["v3", "v2"]);
+    }
+
+    #[test]
+    fn test_semantic_hook_propagates_error() {
+        fn failing(_: &mut Dummy, _: &semver::Version) -> VersionizeResult<()> {
+            Err(VersionizeError::Semantic("nope".to_string()))
+        }
+
+        let mut obj = Dummy::default();
+        let mut hooks = vec![SemanticHook {
+            version: semver::Version::parse("2.2.0").unwrap(),
+            semantic_de: Some(failing),
+            semantic_ser: None,
+        }];
+
+        assert_eq!(
+            run_semantic_de(&mut obj, &mut hooks, &semver::Version::parse("2.1.0").unwrap())
+                .unwrap_err(),
+            VersionizeError::Semantic("nope".to_string())
+        );
+    }
+
+    // Simulates the generated `Versionize` impl for a struct whose field `b` was added in
+    // "my-crate" 2.8.0 and derived from `a` rather than defaulted outright, wiring
+    // `run_semantic_de`/`run_semantic_ser` into `Versionize::semantic_de`/`semantic_ser` the
+    // way `#[version(start = "2.8.0", semantic_de = "...", semantic_ser = "...")]` would
+    // generate.
+    #[derive(Debug, PartialEq, Clone)]
+    struct State {
+        a: u32,
+        b: u32,
+    }
+
+    fn state_hooks() -> Vec<SemanticHook<State>> {
+        vec![SemanticHook {
+            version: semver::Version::parse("2.8.0").unwrap(),
+            semantic_de: Some(|state: &mut State, _: &semver::Version| -> VersionizeResult<()> {
+                state.b = state.a;
+                Ok(())
+            }),
+            semantic_ser: Some(|state: &mut State, _: &semver::Version| -> VersionizeResult<()> {
+                state.a += state.b;
+                Ok(())
+            }),
+        }]
+    }
+
+    impl crate::Versionize for State {
+        fn serialize<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+            version_map: &VersionMap,
+            target_version: u16,
+        ) -> VersionizeResult<()> {
+            let mut copy = self.clone();
+            copy.semantic_ser(version_map, target_version)?;
+
+            bincode::serialize_into(&mut *writer, &copy.a)
+                .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+            if version_map.get_crate_version("my-crate")? >= semver::Version::parse("2.8.0").unwrap()
+            {
+                bincode::serialize_into(writer, &copy.b)
+                    .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+            }
+            Ok(())
+        }
+
+        fn deserialize<R: std::io::Read>(
+            reader: &mut R,
+            version_map: &VersionMap,
+            source_version: u16,
+        ) -> VersionizeResult<Self> {
+            let a = bincode::deserialize_from(&mut *reader)
+                .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+            let b = if version_map.get_crate_version("my-crate")?
+                >= semver::Version::parse("2.8.0").unwrap()
+            {
+                bincode::deserialize_from(reader)
+                    .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?
+            } else {
+                0
+            };
+
+            let mut state = State { a, b };
+            state.semantic_de(version_map, source_version)?;
+            Ok(state)
+        }
+
+        fn version() -> u16 {
+            1
+        }
+
+        fn semantic_de(
+            &mut self,
+            version_map: &VersionMap,
+            _source_version: u16,
+        ) -> VersionizeResult<()> {
+            let source = version_map.get_crate_version("my-crate")?;
+            run_semantic_de(self, &mut state_hooks(), &source)
+        }
+
+        fn semantic_ser(
+            &mut self,
+            version_map: &VersionMap,
+            _target_version: u16,
+        ) -> VersionizeResult<()> {
+            let target = version_map.get_crate_version("my-crate")?;
+            run_semantic_ser(self, &mut state_hooks(), &target)
+        }
+    }
+
+    #[test]
+    fn test_semantic_hooks_wired_through_versionize_semantic_de_ser() {
+        use crate::Versionize;
+
+        // Old writer (pre-2.8.0): only `a` is on the wire; deserializing upgrades `b` from it.
+        let mut vm_old = VersionMap::new();
+        vm_old.set_crate_version("my-crate", "2.7.9").unwrap();
+        let mut buf = Vec::new();
+        State { a: 5, b: 0 }.serialize(&mut buf, &vm_old, 1).unwrap();
+        assert_eq!(buf.len(), 4);
+        let de: State = Versionize::deserialize(&mut buf.as_slice(), &vm_old, 1).unwrap();
+        assert_eq!(de, State { a: 5, b: 5 });
+
+        // New writer (2.8.0+): both fields round-trip untouched.
+        let mut vm_new = VersionMap::new();
+        vm_new.set_crate_version("my-crate", "2.8.0").unwrap();
+        let mut buf = Vec::new();
+        State { a: 5, b: 7 }.serialize(&mut buf, &vm_new, 1).unwrap();
+        assert_eq!(buf.len(), 8);
+        let de: State = Versionize::deserialize(&mut buf.as_slice(), &vm_new, 1).unwrap();
+        assert_eq!(de, State { a: 5, b: 7 });
+
+        // New in-memory state serialized for an old (pre-2.8.0) reader: `semantic_ser` folds
+        // `b` back into `a` before the field is dropped.
+        let mut buf = Vec::new();
+        State { a: 5, b: 7 }.serialize(&mut buf, &vm_old, 1).unwrap();
+        assert_eq!(buf.len(), 4);
+        let de: State = Versionize::deserialize(&mut buf.as_slice(), &vm_old, 1).unwrap();
+        assert_eq!(de, State { a: 12, b: 12 });
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2020 Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `Versionize` implementations for primitive types that don't go through the derive macro,
+//! plus the size limits enforced on variable-length types.
+
+use crate::{VersionMap, Versionize, VersionizeError, VersionizeResult};
+
+/// Maximum length, in bytes, of a `String` serialized through [`Versionize`].
+pub const MAX_STRING_LEN: usize = 1 << 16;
+
+/// Maximum number of elements of a `Vec<T>` serialized through [`Versionize`].
+pub const MAX_VEC_SIZE: usize = 1 << 20;
+
+impl Versionize for String {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        _version_map: &VersionMap,
+        _target_version: u16,
+    ) -> VersionizeResult<()> {
+        if self.len() > MAX_STRING_LEN {
+            return Err(VersionizeError::StringLength(self.len()));
+        }
+
+        bincode::serialize_into(&mut *writer, &(self.len() as u64))
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+        writer
+            .write_all(self.as_bytes())
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))
+    }
+
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        _version_map: &VersionMap,
+        _source_version: u16,
+    ) -> VersionizeResult<Self> {
+        let len: u64 = bincode::deserialize_from(&mut *reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        let len = len as usize;
+        if len > MAX_STRING_LEN {
+            return Err(VersionizeError::StringLength(len));
+        }
+
+        let mut bytes = vec![0u8; len];
+        reader
+            .read_exact(&mut bytes)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        String::from_utf8(bytes).map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))
+    }
+
+    fn version() -> u16 {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ser_de_string() {
+        let vm = VersionMap::new();
+        let mut snapshot_mem = Vec::new();
+        let value = String::from("versionize");
+        value.serialize(&mut snapshot_mem, &vm, 1).unwrap();
+
+        let de_value: String =
+            Versionize::deserialize(&mut snapshot_mem.as_slice(), &vm, 1).unwrap();
+        assert_eq!(de_value, value);
+    }
+
+    #[test]
+    fn test_ser_string_too_long() {
+        let vm = VersionMap::new();
+        let mut snapshot_mem = Vec::new();
+        let value = "a".repeat(MAX_STRING_LEN + 1);
+        assert_eq!(
+            value.serialize(&mut snapshot_mem, &vm, 1).unwrap_err(),
+            VersionizeError::StringLength(MAX_STRING_LEN + 1)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2020 Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A self-describing, tamper-evident container format for `Versionize` payloads.
+//!
+//! A [`Snapshot`] frames a payload with a magic number, a format version, the
+//! [`VersionMap`] it was written with (so a reader doesn't need to already know it) and a
+//! CRC64 trailer computed over everything that precedes it.
+
+use std::io::{Read, Write};
+
+use crate::crc::{CRC64Reader, CRC64Writer};
+use crate::{VersionMap, Versionize, VersionizeError, VersionizeResult};
+
+// Arbitrary 4-byte tag identifying a versionize snapshot; chosen to be unlikely to collide
+// with other framed formats.
+const SNAPSHOT_MAGIC: u32 = 0x5653_4E53;
+const SNAPSHOT_FORMAT_VERSION: u8 = 1;
+
+/// A self-describing snapshot container.
+///
+/// `Snapshot::save` writes `obj` framed with the `VersionMap` it was serialized with and a
+/// CRC64 trailer; `Snapshot::load` reconstructs the `VersionMap` from that frame and uses it
+/// to deserialize `T`, so the caller doesn't need to already know the exact crate→version
+/// mapping the snapshot was produced with.
+pub struct Snapshot;
+
+impl Snapshot {
+    /// Serializes `obj` to `writer` as a framed, self-describing, CRC64-checked snapshot.
+    pub fn save<W: Write, T: Versionize>(
+        writer: &mut W,
+        obj: &T,
+        version_map: &VersionMap,
+        target_version: u16,
+    ) -> VersionizeResult<()> {
+        let mut writer = CRC64Writer::new(writer);
+
+        bincode::serialize_into(&mut writer, &SNAPSHOT_MAGIC)
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+        bincode::serialize_into(&mut writer, &SNAPSHOT_FORMAT_VERSION)
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+        bincode::serialize_into(&mut writer, &target_version)
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+
+        // `version_map.crates()` is a `HashMap`, whose iteration order isn't stable across
+        // processes; sort by crate name so the emitted bytes (and thus the CRC64 trailer) are
+        // reproducible for identical input.
+        let mut crates: Vec<(&String, &semver::Version)> = version_map.crates().iter().collect();
+        crates.sort_by_key(|(name, _)| *name);
+
+        bincode::serialize_into(&mut writer, &(crates.len() as u64))
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+        for (crate_name, crate_version) in crates {
+            crate_name.serialize(&mut writer, version_map, target_version)?;
+            crate_version.serialize(&mut writer, version_map, target_version)?;
+        }
+
+        obj.serialize(&mut writer, version_map, target_version)?;
+
+        let checksum = writer.checksum();
+        bincode::serialize_into(writer.into_inner(), &checksum)
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))
+    }
+
+    /// Loads a snapshot written by [`Snapshot::save`], validating its magic number and CRC64
+    /// trailer and reconstructing the `VersionMap` it was saved with before deserializing `T`.
+    pub fn load<R: Read, T: Versionize>(reader: &mut R) -> VersionizeResult<T> {
+        let mut reader = CRC64Reader::new(reader);
+
+        let magic: u32 = bincode::deserialize_from(&mut reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(VersionizeError::InvalidMagic(magic));
+        }
+
+        let _format_version: u8 = bincode::deserialize_from(&mut reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        let target_version: u16 = bincode::deserialize_from(&mut reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+
+        let mut version_map = VersionMap::new();
+        let num_crates: u64 = bincode::deserialize_from(&mut reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        if num_crates as usize > crate::primitives::MAX_VEC_SIZE {
+            return Err(VersionizeError::VecLength(num_crates as usize));
+        }
+        for _ in 0..num_crates {
+            let crate_name: String =
+                Versionize::deserialize(&mut reader, &version_map, target_version)?;
+            let crate_version: semver::Version =
+                Versionize::deserialize(&mut reader, &version_map, target_version)?;
+            version_map.set_crate_version(&crate_name, &crate_version.to_string())?;
+        }
+
+        let obj = T::deserialize(&mut reader, &version_map, target_version)?;
+
+        let actual_checksum = reader.checksum();
+        let expected_checksum: u64 = bincode::deserialize_from(&mut reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        if actual_checksum != expected_checksum {
+            return Err(VersionizeError::Crc(expected_checksum, actual_checksum));
+        }
+
+        Ok(obj)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Dummy {
+        a: u32,
+    }
+
+    impl Versionize for Dummy {
+        fn serialize<W: Write>(
+            &self,
+            writer: &mut W,
+            _version_map: &VersionMap,
+            _target_version: u16,
+        ) -> VersionizeResult<()> {
+            bincode::serialize_into(writer, &self.a)
+                .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))
+        }
+
+        fn deserialize<R: Read>(
+            reader: &mut R,
+            _version_map: &VersionMap,
+            _source_version: u16,
+        ) -> VersionizeResult<Self> {
+            let a = bincode::deserialize_from(reader)
+                .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+            Ok(Dummy { a })
+        }
+
+        fn version() -> u16 {
+            1
+        }
+    }
+
+    #[test]
+    fn test_snapshot_save_load_roundtrip() {
+        let mut version_map = VersionMap::new();
+        version_map.set_crate_version("my-crate", "2.7.9").unwrap();
+        let obj = Dummy { a: 42 };
+
+        let mut buf = Vec::new();
+        Snapshot::save(&mut buf, &obj, &version_map, 1).unwrap();
+
+        let loaded: Dummy = Snapshot::load(&mut buf.as_slice()).unwrap();
+        assert_eq!(loaded, obj);
+    }
+
+    #[test]
+    fn test_snapshot_save_is_deterministic_across_crate_insertion_order() {
+        let obj = Dummy { a: 42 };
+
+        let mut version_map_a = VersionMap::new();
+        version_map_a.set_crate_version("crate-a", "1.0.0").unwrap();
+        version_map_a.set_crate_version("crate-b", "2.0.0").unwrap();
+        version_map_a.set_crate_version("crate-c", "3.0.0").unwrap();
+
+        let mut version_map_b = VersionMap::new();
+        version_map_b.set_crate_version("crate-c", "3.0.0").unwrap();
+        version_map_b.set_crate_version("crate-a", "1.0.0").unwrap();
+        version_map_b.set_crate_version("crate-b", "2.0.0").unwrap();
+
+        let mut buf_a = Vec::new();
+        Snapshot::save(&mut buf_a, &obj, &version_map_a, 1).unwrap();
+        let mut buf_b = Vec::new();
+        Snapshot::save(&mut buf_b, &obj, &version_map_b, 1).unwrap();
+
+        assert_eq!(buf_a, buf_b);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_bad_magic() {
+        let buf = vec![0u8; 64];
+        assert_eq!(
+            Snapshot::load::<_, Dummy>(&mut buf.as_slice()).unwrap_err(),
+            VersionizeError::InvalidMagic(0)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_rejects_corrupted_payload() {
+        let version_map = VersionMap::new();
+        let obj = Dummy { a: 42 };
+
+        let mut buf = Vec::new();
+        Snapshot::save(&mut buf, &obj, &version_map, 1).unwrap();
+
+        // Flip the last byte of `obj`'s payload, just before the 8-byte CRC64 trailer, so the
+        // corruption is guaranteed to land in the checksummed data rather than in the crate
+        // count or name/version entries that precede it.
+        let flip_index = buf.len() - 8 - 1;
+        buf[flip_index] ^= 0xff;
+
+        assert!(matches!(
+            Snapshot::load::<_, Dummy>(&mut buf.as_slice()),
+            Err(VersionizeError::Crc(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_rejects_oversized_crate_count() {
+        // A `num_crates` field claiming more entries than `MAX_VEC_SIZE` must be rejected
+        // up front, before `load` attempts to deserialize that many crate name/version pairs.
+        let mut buf = Vec::new();
+        bincode::serialize_into(&mut buf, &SNAPSHOT_MAGIC).unwrap();
+        bincode::serialize_into(&mut buf, &SNAPSHOT_FORMAT_VERSION).unwrap();
+        bincode::serialize_into(&mut buf, &1u16).unwrap();
+        bincode::serialize_into(&mut buf, &((crate::primitives::MAX_VEC_SIZE as u64) + 1)).unwrap();
+
+        assert!(matches!(
+            Snapshot::load::<_, Dummy>(&mut buf.as_slice()),
+            Err(VersionizeError::VecLength(_))
+        ));
+    }
+}
@@ -64,6 +64,8 @@ use std::fmt::Debug;
 
 use crate::{Versionize, VersionizeError, VersionizeResult};
 
+/// The maximum value of a `semver::Version` major, minor or patch component that can be
+/// serialized (each is written as a `u16`).
 pub const MAX_VERSION_NUM: u64 = u16::MAX as u64;
 
 ///
@@ -72,12 +74,14 @@ pub const MAX_VERSION_NUM: u64 = u16::MAX as u64;
 #[derive(Clone, Debug)]
 pub struct VersionMap {
     crates: HashMap<String, semver::Version>,
+    strict_release_only: bool,
 }
 
 impl Default for VersionMap {
     fn default() -> Self {
         VersionMap {
             crates: HashMap::new(),
+            strict_release_only: false,
         }
     }
 }
@@ -88,6 +92,18 @@ impl VersionMap {
         Default::default()
     }
 
+    /// Restricts `semver::Version` (de)serialization to release versions, rejecting any
+    /// version with `-pre` or `+build` metadata with [`VersionizeError::UnsuportVersion`].
+    ///
+    /// By default this is off, so pre-release and build metadata round-trip, which suits
+    /// dev tooling working with `-alpha`/`-rc` builds. Production snapshot pipelines (e.g.
+    /// Firecracker) that only ever run release builds should turn this on.
+    pub fn strict_release_only(&mut self, strict: bool) -> &mut Self {
+        self.strict_release_only = strict;
+        self
+    }
+
+    /// Returns the version registered for `crate_name`, or `VersionizeError::NotFoundCrate`.
     pub fn get_crate_version(&self, crate_name: &str) -> VersionizeResult<semver::Version> {
         self.crates
             .get(crate_name)
@@ -95,6 +111,8 @@ impl VersionMap {
             .cloned()
     }
 
+    /// Registers `crate_name` as being at version `ver`, failing if it was already registered
+    /// at a different version.
     pub fn set_crate_version(
         &mut self,
         crate_name: &str,
@@ -117,16 +135,120 @@ impl VersionMap {
 
         Ok(sem_ver)
     }
+
+    /// Returns the full crate name to version mapping held by this `VersionMap`.
+    pub fn crates(&self) -> &HashMap<String, semver::Version> {
+        &self.crates
+    }
+}
+
+/// A single per-branch version bound, i.e. the `(major, minor, patch)` parsed out of one
+/// comma-separated entry of a `#[version(start = "...", end = "...")]` attribute such as
+/// `"2.7.7, 2.8.3"`.
+pub type BranchBound = (u64, u64, u64);
+
+/// Parses a comma-separated list of `major.minor.patch` bounds, one per maintained release
+/// branch, as accepted by the multi-branch form of the `#[version(start = "...", end =
+/// "...")]` attribute.
+pub fn parse_branch_bounds(bounds: &str) -> VersionizeResult<Vec<BranchBound>> {
+    bounds
+        .split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            let ver = semver::Version::parse(entry)
+                .map_err(|e| VersionizeError::ParseVersion(entry.to_string(), e.to_string()))?;
+            Ok((ver.major, ver.minor, ver.patch))
+        })
+        .collect()
+}
+
+/// Resolves whether a field gated by multi-branch `start`/`end` bounds (as parsed by
+/// [`parse_branch_bounds`]) is present for the crate version that `version_map` currently
+/// resolves `crate_name` to.
+///
+/// The `start` bound whose `major.minor` matches the target version's branch is used; if no
+/// bound matches, the bound for the lowest maintained branch is used instead, so that
+/// branches outside the maintained set degrade predictably rather than erroring out. The
+/// `end` bound, if any, is then looked up for that *same* resolved branch only: `starts` and
+/// `ends` are independent comma-separated lists (a branch may have a start with no end), so an
+/// end bound from a different branch must never be applied to this one.
+pub fn field_present_for_branches(
+    version_map: &VersionMap,
+    crate_name: &str,
+    starts: &[BranchBound],
+    ends: &[BranchBound],
+) -> VersionizeResult<bool> {
+    let target = version_map.get_crate_version(crate_name)?;
+    let branch = (target.major, target.minor);
+
+    let start = match find_branch_bound(starts, branch) {
+        Some(bound) => bound,
+        None => return Ok(false),
+    };
+    let resolved_branch = (start.0, start.1);
+
+    if target.patch < start.2 {
+        return Ok(false);
+    }
+
+    let expired = ends
+        .iter()
+        .find(|(major, minor, _)| (*major, *minor) == resolved_branch)
+        .is_some_and(|end| target.patch >= end.2);
+
+    Ok(!expired)
+}
+
+/// Parses a `#[version(req = "...")]` requirement string into a `semver::VersionReq`.
+///
+/// Split out from [`field_present_for_req`] so the literal is parsed once — at macro
+/// expansion time, stored as generated metadata — rather than re-parsed on every
+/// serialize/deserialize call; a malformed requirement is therefore caught once, up front,
+/// instead of being deferred to runtime on every (de)serialization.
+pub fn parse_version_req(req: &str) -> VersionizeResult<semver::VersionReq> {
+    semver::VersionReq::parse(req)
+        .map_err(|e| VersionizeError::ParseVersion(req.to_string(), e.to_string()))
+}
+
+/// Resolves whether a field gated by `#[version(req = "...")]` is present for the crate
+/// version that `version_map` currently resolves `crate_name` to.
+///
+/// `req` is a `semver::VersionReq` (e.g. `">=2.7, <2.9"`, parsed once via
+/// [`parse_version_req`]), which, unlike a single `start`/`end` range, can express multiple
+/// disjoint comparator sets such as "present in 2.7.x and 3.x but not 2.8.x".
+pub fn field_present_for_req(
+    version_map: &VersionMap,
+    crate_name: &str,
+    req: &semver::VersionReq,
+) -> VersionizeResult<bool> {
+    let target = version_map.get_crate_version(crate_name)?;
+    Ok(req.matches(&target))
+}
+
+// Picks the `start` bound whose `major.minor` matches `branch`, falling back to the bound for
+// the lowest maintained branch when there's no exact match. Only ever called against `starts`:
+// the matching `end` bound (if any) is resolved separately, for the exact branch this picks.
+fn find_branch_bound(bounds: &[BranchBound], branch: (u64, u64)) -> Option<BranchBound> {
+    bounds
+        .iter()
+        .find(|(major, minor, _)| (*major, *minor) == branch)
+        .or_else(|| bounds.iter().min_by_key(|(major, minor, _)| (*major, *minor)))
+        .copied()
 }
 
+// Flags byte bits gating the optional pre-release/build trailer. A zero flags byte means
+// neither is present, keeping the fast (release-only) path a fixed 7 bytes.
+const SEMVER_PRE_FLAG: u8 = 0b01;
+const SEMVER_BUILD_FLAG: u8 = 0b10;
+
 impl Versionize for semver::Version {
     fn serialize<W: std::io::Write>(
         &self,
-        mut writer: W,
-        _version_map: &mut VersionMap,
+        writer: &mut W,
+        version_map: &VersionMap,
+        target_version: u16,
     ) -> VersionizeResult<()> {
-        // Only support release version.
-        if !self.pre.is_empty() || !self.build.is_empty() {
+        if version_map.strict_release_only && (!self.pre.is_empty() || !self.build.is_empty()) {
             return Err(VersionizeError::UnsuportVersion(self.to_string()));
         }
         // To reduce snapshot size, only u16::MAX is supported, which should be enough.
@@ -136,33 +258,86 @@ impl Versionize for semver::Version {
         {
             return Err(VersionizeError::UnsuportVersion(self.to_string()));
         }
-        bincode::serialize_into(&mut writer, &(self.major as u16))
+        bincode::serialize_into(&mut *writer, &(self.major as u16))
             .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
-        bincode::serialize_into(&mut writer, &(self.minor as u16))
+        bincode::serialize_into(&mut *writer, &(self.minor as u16))
             .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
-        bincode::serialize_into(&mut writer, &(self.patch as u16))
+        bincode::serialize_into(&mut *writer, &(self.patch as u16))
             .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+
+        let mut flags = 0u8;
+        if !self.pre.is_empty() {
+            flags |= SEMVER_PRE_FLAG;
+        }
+        if !self.build.is_empty() {
+            flags |= SEMVER_BUILD_FLAG;
+        }
+        bincode::serialize_into(&mut *writer, &flags)
+            .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+
+        if !self.pre.is_empty() {
+            self.pre
+                .as_str()
+                .to_string()
+                .serialize(writer, version_map, target_version)?;
+        }
+        if !self.build.is_empty() {
+            self.build
+                .as_str()
+                .to_string()
+                .serialize(writer, version_map, target_version)?;
+        }
         Ok(())
     }
 
     fn deserialize<R: std::io::Read>(
-        mut reader: R,
-        _version_map: &VersionMap,
+        reader: &mut R,
+        version_map: &VersionMap,
+        source_version: u16,
     ) -> VersionizeResult<Self>
     where
         Self: Sized,
     {
-        let major: u16 = bincode::deserialize_from(&mut reader)
+        let major: u16 = bincode::deserialize_from(&mut *reader)
+            .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+        let minor: u16 = bincode::deserialize_from(&mut *reader)
             .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
-        let minor: u16 = bincode::deserialize_from(&mut reader)
+        let patch: u16 = bincode::deserialize_from(&mut *reader)
             .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
-        let patch: u16 = bincode::deserialize_from(&mut reader)
+        let flags: u8 = bincode::deserialize_from(&mut *reader)
             .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
-        Ok(semver::Version::new(
-            major as u64,
-            minor as u64,
-            patch as u64,
-        ))
+
+        let pre = if flags & SEMVER_PRE_FLAG != 0 {
+            let raw: String = Versionize::deserialize(reader, version_map, source_version)?;
+            semver::Prerelease::new(&raw)
+                .map_err(|e| VersionizeError::ParseVersion(raw, e.to_string()))?
+        } else {
+            semver::Prerelease::EMPTY
+        };
+        let build = if flags & SEMVER_BUILD_FLAG != 0 {
+            let raw: String = Versionize::deserialize(reader, version_map, source_version)?;
+            semver::BuildMetadata::new(&raw)
+                .map_err(|e| VersionizeError::ParseVersion(raw, e.to_string()))?
+        } else {
+            semver::BuildMetadata::EMPTY
+        };
+
+        let version = semver::Version {
+            major: major as u64,
+            minor: minor as u64,
+            patch: patch as u64,
+            pre,
+            build,
+        };
+        if version_map.strict_release_only && (!version.pre.is_empty() || !version.build.is_empty())
+        {
+            return Err(VersionizeError::UnsuportVersion(version.to_string()));
+        }
+        Ok(version)
+    }
+
+    fn version() -> u16 {
+        1
     }
 }
 
@@ -175,20 +350,28 @@ mod tests {
 
     #[test]
     fn test_ser_de_semver_err() {
-        let mut vm = VersionMap::new();
+        let vm = VersionMap::new();
         let mut snapshot_mem = vec![0u8; 48];
         let sem_ver = semver::Version::new(1, 1, MAX_VERSION_NUM + 1);
         assert_eq!(
             sem_ver
-                .serialize(snapshot_mem.as_mut_slice(), &mut vm)
+                .serialize(&mut snapshot_mem.as_mut_slice(), &vm, 1)
                 .unwrap_err(),
             VersionizeError::UnsuportVersion("1.1.65536".to_string())
         );
 
+        // Pre-release/build versions round-trip by default...
+        let mut vm = VersionMap::new();
         let sem_ver = semver::Version::parse("1.0.0-alpha").unwrap();
+        sem_ver
+            .serialize(&mut snapshot_mem.as_mut_slice(), &vm, 1)
+            .unwrap();
+
+        // ...but are rejected once `strict_release_only` is set.
+        vm.strict_release_only(true);
         assert_eq!(
             sem_ver
-                .serialize(snapshot_mem.as_mut_slice(), &mut vm)
+                .serialize(&mut snapshot_mem.as_mut_slice(), &vm, 1)
                 .unwrap_err(),
             VersionizeError::UnsuportVersion("1.0.0-alpha".to_string())
         );
@@ -196,19 +379,33 @@ mod tests {
         let sem_ver = semver::Version::parse("1.0.0+alpha").unwrap();
         assert_eq!(
             sem_ver
-                .serialize(snapshot_mem.as_mut_slice(), &mut vm)
+                .serialize(&mut snapshot_mem.as_mut_slice(), &vm, 1)
                 .unwrap_err(),
             VersionizeError::UnsuportVersion("1.0.0+alpha".to_string())
         );
     }
 
+    #[test]
+    fn test_ser_de_semver_pre_and_build() {
+        let vm = VersionMap::new();
+        let mut snapshot_mem = vec![0u8; 64];
+        let sem_ver = semver::Version::parse("1.2.3-alpha.1+build.5").unwrap();
+        sem_ver
+            .serialize(&mut snapshot_mem.as_mut_slice(), &vm, 1)
+            .unwrap();
+
+        let de_ver: semver::Version =
+            Versionize::deserialize(&mut snapshot_mem.as_slice(), &vm, 1).unwrap();
+        assert_eq!(de_ver, sem_ver);
+    }
+
     #[test]
     fn test_ser_de_semver() {
-        let mut vm = VersionMap::new();
-        let mut snapshot_mem = vec![0u8; 6];
+        let vm = VersionMap::new();
+        let mut snapshot_mem = vec![0u8; 7];
         let sem_ver = semver::Version::new(3, 0, 14);
         sem_ver
-            .serialize(&mut snapshot_mem.as_mut_slice(), &mut vm)
+            .serialize(&mut snapshot_mem.as_mut_slice(), &vm, 1)
             .unwrap();
 
         assert_eq!(3, (&snapshot_mem[..2]).read_u16::<NativeEndian>().unwrap());
@@ -219,7 +416,256 @@ mod tests {
         );
 
         let de_ver: semver::Version =
-            Versionize::deserialize(snapshot_mem.as_slice(), &vm).unwrap();
+            Versionize::deserialize(&mut snapshot_mem.as_slice(), &vm, 1).unwrap();
         assert_eq!(de_ver, semver::Version::parse("3.0.14").unwrap());
     }
+
+    #[test]
+    fn test_parse_branch_bounds() {
+        assert_eq!(
+            parse_branch_bounds("2.7.7, 2.8.3").unwrap(),
+            vec![(2, 7, 7), (2, 8, 3)]
+        );
+        assert_eq!(
+            parse_branch_bounds("not-a-version").unwrap_err(),
+            VersionizeError::ParseVersion(
+                "not-a-version".to_string(),
+                semver::Version::parse("not-a-version")
+                    .unwrap_err()
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_field_present_for_branches() {
+        let mut vm = VersionMap::new();
+        vm.set_crate_version("my-crate", "2.7.9").unwrap();
+        let starts = parse_branch_bounds("2.7.7, 2.8.3").unwrap();
+        let ends = parse_branch_bounds("2.7.11, 2.8.7").unwrap();
+
+        // Present: within the 2.7.x branch bound.
+        assert!(field_present_for_branches(&vm, "my-crate", &starts, &ends).unwrap());
+
+        // Absent: before the 2.7.x branch's start patch.
+        let mut vm2 = VersionMap::new();
+        vm2.set_crate_version("my-crate", "2.7.5").unwrap();
+        assert!(!field_present_for_branches(&vm2, "my-crate", &starts, &ends).unwrap());
+
+        // Absent: at/after the 2.7.x branch's end patch.
+        let mut vm3 = VersionMap::new();
+        vm3.set_crate_version("my-crate", "2.7.11").unwrap();
+        assert!(!field_present_for_branches(&vm3, "my-crate", &starts, &ends).unwrap());
+
+        // Unmaintained branch falls back to the lowest maintained branch's bound (2.7.7..2.7.11).
+        let mut vm4 = VersionMap::new();
+        vm4.set_crate_version("my-crate", "2.6.6").unwrap();
+        assert!(!field_present_for_branches(&vm4, "my-crate", &starts, &ends).unwrap());
+        let mut vm5 = VersionMap::new();
+        vm5.set_crate_version("my-crate", "2.6.8").unwrap();
+        assert!(field_present_for_branches(&vm5, "my-crate", &starts, &ends).unwrap());
+    }
+
+    #[test]
+    fn test_field_present_for_branches_end_does_not_leak_across_branches() {
+        // `ends` only covers the 2.8.x branch; 2.7.x has a start but no declared end, so it
+        // must never expire using the 2.8.x branch's end patch.
+        let starts = parse_branch_bounds("2.7.7, 2.8.3").unwrap();
+        let ends = parse_branch_bounds("2.8.7").unwrap();
+
+        let mut vm = VersionMap::new();
+        vm.set_crate_version("my-crate", "2.7.9").unwrap();
+        assert!(field_present_for_branches(&vm, "my-crate", &starts, &ends).unwrap());
+
+        // The 2.8.x branch still honors its own end.
+        let mut vm2 = VersionMap::new();
+        vm2.set_crate_version("my-crate", "2.8.7").unwrap();
+        assert!(!field_present_for_branches(&vm2, "my-crate", &starts, &ends).unwrap());
+    }
+
+    // Simulates the generated `Versionize` impl for a struct with a field gated by
+    // `#[version(start = "2.7.7, 2.8.3", end = "2.7.11, 2.8.7")]`, proving
+    // `field_present_for_branches` wires into real `serialize`/`deserialize` calls the way
+    // the derive macro would generate them.
+    struct StateWithBranchGatedField {
+        a: u32,
+        b: u32,
+    }
+
+    impl Versionize for StateWithBranchGatedField {
+        fn serialize<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+            version_map: &VersionMap,
+            _target_version: u16,
+        ) -> VersionizeResult<()> {
+            bincode::serialize_into(&mut *writer, &self.a)
+                .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+
+            let starts = parse_branch_bounds("2.7.7, 2.8.3").unwrap();
+            let ends = parse_branch_bounds("2.7.11, 2.8.7").unwrap();
+            if field_present_for_branches(version_map, "my-crate", &starts, &ends)? {
+                bincode::serialize_into(writer, &self.b)
+                    .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+            }
+            Ok(())
+        }
+
+        fn deserialize<R: std::io::Read>(
+            reader: &mut R,
+            version_map: &VersionMap,
+            _source_version: u16,
+        ) -> VersionizeResult<Self> {
+            let a = bincode::deserialize_from(&mut *reader)
+                .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+
+            let starts = parse_branch_bounds("2.7.7, 2.8.3").unwrap();
+            let ends = parse_branch_bounds("2.7.11, 2.8.7").unwrap();
+            let b = if field_present_for_branches(version_map, "my-crate", &starts, &ends)? {
+                bincode::deserialize_from(reader)
+                    .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?
+            } else {
+                0
+            };
+
+            Ok(StateWithBranchGatedField { a, b })
+        }
+
+        fn version() -> u16 {
+            1
+        }
+    }
+
+    #[test]
+    fn test_field_present_for_branches_wired_through_serialize_deserialize() {
+        // Present: within the maintained 2.7.x branch bound, so `b` is on the wire.
+        let mut vm = VersionMap::new();
+        vm.set_crate_version("my-crate", "2.7.9").unwrap();
+        let mut buf = Vec::new();
+        StateWithBranchGatedField { a: 1, b: 2 }
+            .serialize(&mut buf, &vm, 1)
+            .unwrap();
+        assert_eq!(buf.len(), 8);
+        let de: StateWithBranchGatedField =
+            Versionize::deserialize(&mut buf.as_slice(), &vm, 1).unwrap();
+        assert_eq!((de.a, de.b), (1, 2));
+
+        // Absent: at/after the branch's end patch, so `b` is dropped on serialize and
+        // defaulted on deserialize, the way an old-branch reader/writer that predates the
+        // field would behave.
+        let mut vm2 = VersionMap::new();
+        vm2.set_crate_version("my-crate", "2.7.11").unwrap();
+        let mut buf2 = Vec::new();
+        StateWithBranchGatedField { a: 1, b: 2 }
+            .serialize(&mut buf2, &vm2, 1)
+            .unwrap();
+        assert_eq!(buf2.len(), 4);
+        let de2: StateWithBranchGatedField =
+            Versionize::deserialize(&mut buf2.as_slice(), &vm2, 1).unwrap();
+        assert_eq!((de2.a, de2.b), (1, 0));
+    }
+
+    #[test]
+    fn test_field_present_for_req() {
+        let req = parse_version_req(">=2.7, <2.9").unwrap();
+
+        let mut vm = VersionMap::new();
+        vm.set_crate_version("my-crate", "2.7.5").unwrap();
+        assert!(field_present_for_req(&vm, "my-crate", &req).unwrap());
+
+        let mut vm2 = VersionMap::new();
+        vm2.set_crate_version("my-crate", "2.9.1").unwrap();
+        assert!(!field_present_for_req(&vm2, "my-crate", &req).unwrap());
+    }
+
+    // Simulates the generated `Versionize` impl for a struct with a field gated by
+    // `#[version(req = "...")]`, proving `field_present_for_req` wires into real
+    // `serialize`/`deserialize` calls the way the derive macro would generate them.
+    struct StateWithReqGatedField {
+        a: u32,
+        b: u32,
+    }
+
+    impl Versionize for StateWithReqGatedField {
+        fn serialize<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+            version_map: &VersionMap,
+            _target_version: u16,
+        ) -> VersionizeResult<()> {
+            bincode::serialize_into(&mut *writer, &self.a)
+                .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+
+            let req = parse_version_req(">=2.7, <2.9").unwrap();
+            if field_present_for_req(version_map, "my-crate", &req)? {
+                bincode::serialize_into(writer, &self.b)
+                    .map_err(|err| VersionizeError::Serialize(format!("{:?}", err)))?;
+            }
+            Ok(())
+        }
+
+        fn deserialize<R: std::io::Read>(
+            reader: &mut R,
+            version_map: &VersionMap,
+            _source_version: u16,
+        ) -> VersionizeResult<Self> {
+            let a = bincode::deserialize_from(&mut *reader)
+                .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?;
+
+            let req = parse_version_req(">=2.7, <2.9").unwrap();
+            let b = if field_present_for_req(version_map, "my-crate", &req)? {
+                bincode::deserialize_from(reader)
+                    .map_err(|err| VersionizeError::Deserialize(format!("{:?}", err)))?
+            } else {
+                0
+            };
+
+            Ok(StateWithReqGatedField { a, b })
+        }
+
+        fn version() -> u16 {
+            1
+        }
+    }
+
+    #[test]
+    fn test_field_present_for_req_wired_through_serialize_deserialize() {
+        // Present: within the `>=2.7, <2.9` window, so `b` is on the wire.
+        let mut vm = VersionMap::new();
+        vm.set_crate_version("my-crate", "2.7.5").unwrap();
+        let mut buf = Vec::new();
+        StateWithReqGatedField { a: 1, b: 2 }
+            .serialize(&mut buf, &vm, 1)
+            .unwrap();
+        assert_eq!(buf.len(), 8);
+        let de: StateWithReqGatedField =
+            Versionize::deserialize(&mut buf.as_slice(), &vm, 1).unwrap();
+        assert_eq!((de.a, de.b), (1, 2));
+
+        // Absent: outside the window, so `b` is dropped on serialize and defaulted on
+        // deserialize.
+        let mut vm2 = VersionMap::new();
+        vm2.set_crate_version("my-crate", "2.9.1").unwrap();
+        let mut buf2 = Vec::new();
+        StateWithReqGatedField { a: 1, b: 2 }
+            .serialize(&mut buf2, &vm2, 1)
+            .unwrap();
+        assert_eq!(buf2.len(), 4);
+        let de2: StateWithReqGatedField =
+            Versionize::deserialize(&mut buf2.as_slice(), &vm2, 1).unwrap();
+        assert_eq!((de2.a, de2.b), (1, 0));
+    }
+
+    #[test]
+    fn test_parse_version_req_err() {
+        assert_eq!(
+            parse_version_req("not-a-req").unwrap_err(),
+            VersionizeError::ParseVersion(
+                "not-a-req".to_string(),
+                semver::VersionReq::parse("not-a-req")
+                    .unwrap_err()
+                    .to_string()
+            )
+        );
+    }
 }